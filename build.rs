@@ -0,0 +1,6 @@
+//! Generates compile-time build metadata (git commit, build timestamp, target triple, rustc
+//! version, enabled features) for use in `--version --verbose` output.
+
+fn main() {
+    built::write_built_file().expect("Failed to acquire build-time information");
+}
@@ -0,0 +1,182 @@
+//! Downloading, extracting and searching the local tldr-pages cache.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use app_dirs::{get_app_dir, AppDataType};
+use flate2::read::GzDecoder;
+use log::debug;
+use tar::Archive;
+use walkdir::WalkDir;
+
+use crate::error::TealdeerError::{self, CacheError, UpdateError};
+use crate::types::OsType;
+use crate::APP_INFO;
+
+const CACHE_DIR_NAME: &str = "tealdeer";
+/// Pages that apply regardless of operating system live in this directory, both in the
+/// English tree and in every `pages.<lang>` translation tree.
+const TLDR_PAGES_COMMON_DIR: &str = "common";
+
+pub struct Cache {
+    url: String,
+    os: OsType,
+}
+
+impl Cache {
+    pub fn new<S>(url: S, os: OsType) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            url: url.into(),
+            os,
+        }
+    }
+
+    /// Return the path to the cache directory. The directory itself is not created.
+    fn ensure_cache_dir() -> Result<PathBuf, TealdeerError> {
+        get_app_dir(AppDataType::UserCache, &APP_INFO, CACHE_DIR_NAME)
+            .map_err(|e| CacheError(format!("Could not get cache directory: {}", e)))
+    }
+
+    /// Download the tarball from `self.url` and unpack it into the cache directory.
+    pub fn update(&self) -> Result<(), TealdeerError> {
+        let cache_dir = Self::ensure_cache_dir()?;
+
+        debug!("Downloading tldr pages from {}", self.url);
+        let mut response = reqwest::blocking::get(&self.url)
+            .map_err(|e| UpdateError(format!("Could not download tldr pages: {}", e)))?;
+        let mut buf: Vec<u8> = vec![];
+        response
+            .copy_to(&mut buf)
+            .map_err(|e| UpdateError(format!("Could not read response: {}", e)))?;
+
+        let decoder = GzDecoder::new(Cursor::new(buf));
+        let mut archive = Archive::new(decoder);
+        archive
+            .unpack(&cache_dir)
+            .map_err(|e| UpdateError(format!("Could not unpack tarball: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Delete the entire cache directory.
+    pub fn clear() -> Result<(), TealdeerError> {
+        let cache_dir = Self::ensure_cache_dir()?;
+        if cache_dir.exists() {
+            fs::remove_dir_all(&cache_dir)
+                .map_err(|e| CacheError(format!("Could not remove cache directory: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Return how long ago the cache was last updated, or `None` if it was never updated.
+    pub fn last_update() -> Option<Duration> {
+        if let Ok(cache_dir) = Self::ensure_cache_dir() {
+            if let Ok(metadata) = fs::metadata(cache_dir) {
+                if let Ok(mtime) = metadata.modified() {
+                    return mtime.elapsed().ok();
+                }
+            }
+        }
+        None
+    }
+
+    /// Return the ordered, de-duplicated list of `pages[.<lang>]` directory names to search,
+    /// most specific first, always ending in the untranslated English `pages` directory.
+    ///
+    /// This mirrors POSIX locale fallback: a language list of `["de", "fr"]` is searched as
+    /// `pages.de`, `pages.fr`, `pages`.
+    fn page_dirs(&self, languages: &[String]) -> Vec<String> {
+        let mut dirs: Vec<String> = vec![];
+        for lang in languages.iter().filter(|lang| lang.as_str() != "en") {
+            let dir = format!("pages.{}", lang);
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        dirs.push("pages".to_string());
+        dirs
+    }
+
+    /// Search the cache for a page matching `name`, trying each language directory (most
+    /// specific language first) and, within each, the OS-specific directory before the
+    /// common one, then falling back to the next language.
+    pub fn find_page(&self, name: &str, languages: &[String]) -> Option<PathBuf> {
+        let cache_dir = Self::ensure_cache_dir().ok()?;
+
+        for page_dir in self.page_dirs(languages) {
+            for subdir in &[self.os.to_string(), TLDR_PAGES_COMMON_DIR.to_string()] {
+                let path = cache_dir
+                    .join(&page_dir)
+                    .join(subdir)
+                    .join(format!("{}.md", name));
+                if path.exists() {
+                    return Some(path);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// List all command names available in the selected language directories, unioned with
+    /// the English ones.
+    pub fn list_pages(&self, languages: &[String]) -> Result<Vec<String>, TealdeerError> {
+        let cache_dir = Self::ensure_cache_dir()?;
+        let mut pages = vec![];
+
+        for page_dir in self.page_dirs(languages) {
+            let dir = cache_dir.join(&page_dir);
+            if !dir.exists() {
+                continue;
+            }
+            for entry in WalkDir::new(&dir).into_iter().filter_map(Result::ok) {
+                if entry.file_type().is_file()
+                    && entry.path().extension().and_then(|ext| ext.to_str()) == Some("md")
+                {
+                    if let Some(stem) = entry.path().file_stem() {
+                        pages.push(stem.to_string_lossy().into_owned());
+                    }
+                }
+            }
+        }
+
+        pages.sort();
+        pages.dedup();
+        Ok(pages)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Cache;
+    use crate::types::OsType;
+
+    #[test]
+    fn test_page_dirs_prepends_languages_and_always_ends_in_english() {
+        let cache = Cache::new("https://example.com/archive.tar.gz", OsType::Linux);
+        assert_eq!(
+            cache.page_dirs(&["de".to_string(), "fr".to_string()]),
+            vec!["pages.de".to_string(), "pages.fr".to_string(), "pages".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_page_dirs_skips_explicit_english() {
+        let cache = Cache::new("https://example.com/archive.tar.gz", OsType::Linux);
+        assert_eq!(
+            cache.page_dirs(&["en".to_string()]),
+            vec!["pages".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_page_dirs_with_no_languages_is_just_english() {
+        let cache = Cache::new("https://example.com/archive.tar.gz", OsType::Linux);
+        assert_eq!(cache.page_dirs(&[]), vec!["pages".to_string()]);
+    }
+}
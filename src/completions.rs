@@ -0,0 +1,16 @@
+//! Shell completion script generation, driven by the `completions` subcommand.
+
+use std::io;
+
+use clap::{App, Shell};
+
+/// Write a completion script for `shell_name` to stdout, using `app` (already built with any
+/// cached page names attached as `possible_values` on the `command` argument, so that e.g.
+/// bash's `<TAB>` offers them too).
+pub fn generate(app: &mut App, shell_name: &str) {
+    let shell: Shell = shell_name.parse().unwrap_or_else(|_| {
+        eprintln!("Unsupported shell: {}", shell_name);
+        std::process::exit(1);
+    });
+    app.gen_completions_to("tldr", shell, &mut io::stdout());
+}
@@ -0,0 +1,85 @@
+//! Configuration file handling.
+
+use std::fs;
+use std::path::PathBuf;
+
+use app_dirs::{app_root, AppDataType};
+use serde::Deserialize;
+
+use crate::error::TealdeerError::{self, ConfigError};
+use crate::APP_INFO;
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DisplayConfig {
+    #[serde(default)]
+    pub use_pager: bool,
+    #[serde(default)]
+    pub compact: bool,
+    /// Explicit pager command (e.g. `"bat"`, `"more"`). Takes precedence over `$PAGER` /
+    /// `$MANPAGER` and the built-in `less -R` default.
+    #[serde(default)]
+    pub pager_command: Option<String>,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            use_pager: false,
+            compact: false,
+            pager_command: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub display: DisplayConfig,
+    /// Whether ANSI styling is enabled for this invocation. This is not read from the config
+    /// file; it's the resolved `--color`/`NO_COLOR`/TTY decision passed in by the caller.
+    #[serde(skip)]
+    pub styles_enabled: bool,
+}
+
+/// Return the path to the config file, creating the containing directory if necessary.
+pub fn get_config_path() -> Result<PathBuf, TealdeerError> {
+    let config_dir = app_root(AppDataType::UserConfig, &APP_INFO)
+        .map_err(|e| ConfigError(format!("Could not get config directory: {}", e)))?;
+    Ok(config_dir.join(CONFIG_FILE_NAME))
+}
+
+impl Config {
+    /// Load the config file, if present, falling back to defaults for anything it doesn't
+    /// specify. `styles_enabled` carries the already-resolved color decision through to the
+    /// formatter.
+    pub fn load(styles_enabled: bool) -> Result<Self, TealdeerError> {
+        let path = get_config_path()?;
+
+        let mut config = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| ConfigError(format!("Could not read config file: {}", e)))?;
+            toml::from_str(&contents)
+                .map_err(|e| ConfigError(format!("Could not parse config file: {}", e)))?
+        } else {
+            Self::default()
+        };
+
+        config.styles_enabled = styles_enabled;
+        Ok(config)
+    }
+}
+
+/// Write a config file holding only a header comment to the config path (replacing any
+/// existing file there) and return that path.
+pub fn make_default_config() -> Result<PathBuf, TealdeerError> {
+    let path = get_config_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ConfigError(format!("Could not create config directory: {}", e)))?;
+    }
+    fs::write(&path, "# tealdeer configuration file\n")
+        .map_err(|e| ConfigError(format!("Could not write config file: {}", e)))?;
+    Ok(path)
+}
@@ -0,0 +1,18 @@
+//! Error type shared by all tealdeer modules.
+
+use quick_error::quick_error;
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum TealdeerError {
+        CacheError(msg: String) {
+            display("Cache error: {}", msg)
+        }
+        ConfigError(msg: String) {
+            display("Config error: {}", msg)
+        }
+        UpdateError(msg: String) {
+            display("Update error: {}", msg)
+        }
+    }
+}
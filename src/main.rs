@@ -26,6 +26,7 @@ use std::time::Duration;
 
 use ansi_term::Color;
 use app_dirs::AppInfo;
+use atty::Stream;
 use docopt::Docopt;
 use clap::{Arg, App, SubCommand};
 #[cfg(not(target_os = "windows"))]
@@ -33,6 +34,7 @@ use pager::Pager;
 use serde_derive::Deserialize;
 
 mod cache;
+mod completions;
 mod config;
 mod error;
 mod formatter;
@@ -44,7 +46,13 @@ use crate::config::{get_config_path, make_default_config, Config};
 use crate::error::TealdeerError::{CacheError, ConfigError, UpdateError};
 use crate::formatter::print_lines;
 use crate::tokenizer::Tokenizer;
-use crate::types::OsType;
+use crate::types::{ColorWhen, OsType};
+
+/// Compile-time build metadata (git commit, build timestamp, target triple, rustc version,
+/// enabled features), generated by `build.rs` via the `built` crate.
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
 
 const NAME: &str = "tealdeer";
 const APP_INFO: AppInfo = AppInfo {
@@ -62,12 +70,15 @@ Options:
 
     -h --help           Show this screen
     -v --version        Show version information
+    --verbose           Used with --version, show build metadata (commit, target, features)
     -l --list           List all commands in the cache
     -f --render <file>  Render a specific markdown file
     -o --os <type>      Override the operating system [linux, osx, sunos, windows]
+    -L --language <lang>  Override the language (e.g. `de`, `pt_BR`)
     -u --update         Update the local cache
     -c --clear-cache    Clear the local cache
     -p --pager          Use a pager to page output
+    --color <when>      Control whether to use colors [auto, always, never]
     -m --markdown       Display the raw markdown instead of rendering it
     -q --quiet          Suppress informational messages
     --config-path       Show config file path
@@ -97,18 +108,112 @@ struct Args {
     arg_command: Option<Vec<String>>,
     flag_help: bool,
     flag_version: bool,
+    flag_verbose: bool,
     flag_list: bool,
     flag_render: Option<String>,
     flag_os: Option<OsType>,
+    flag_language: Option<String>,
     flag_update: bool,
     flag_clear_cache: bool,
     flag_pager: bool,
+    flag_color: Option<ColorWhen>,
     flag_quiet: bool,
     flag_config_path: bool,
     flag_seed_config: bool,
     flag_markdown: bool,
 }
 
+/// Resolve the list of languages to search, most specific first.
+///
+/// If `--language` was given, that language alone is used. Otherwise the `LANGUAGE`
+/// environment variable (a colon-separated priority list, e.g. `de:fr`) is consulted, falling
+/// back to the single-language `LANG` variable (stripping any `.UTF-8`-style encoding suffix).
+/// English is implicitly appended last by `Cache::page_dirs`, so it doesn't need to appear
+/// here.
+fn resolve_languages(flag_language: &Option<String>) -> Vec<String> {
+    if let Some(ref lang) = flag_language {
+        return vec![lang.clone()];
+    }
+
+    if let Ok(language) = std::env::var("LANGUAGE") {
+        let langs: Vec<String> = language
+            .split(':')
+            .map(str::to_string)
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !langs.is_empty() {
+            return langs;
+        }
+    }
+
+    if let Ok(lang) = std::env::var("LANG") {
+        // Strip the `.UTF-8`-style encoding suffix, e.g. `de_DE.UTF-8` -> `de_DE`.
+        let lang = lang.split('.').next().unwrap_or(&lang).to_string();
+        if !lang.is_empty() && lang != "C" && lang != "POSIX" {
+            // A territory'd locale like `de_DE` isn't shipped as its own `pages.<lang>`
+            // directory, so also try the bare language prefix (`de`) as a fallback.
+            match lang.split_once('_') {
+                Some((bare, _)) => return vec![lang.clone(), bare.to_string()],
+                None => return vec![lang],
+            }
+        }
+    }
+
+    vec![]
+}
+
+/// Resolve whether ANSI styling should be used, based on the `--color` flag, the `NO_COLOR`
+/// environment variable, and whether stdout is a TTY.
+///
+/// `always` forces styling on (even through the pager); `never` forces it off; `auto` (the
+/// default) enables styling unless `NO_COLOR` is set or stdout has been redirected.
+fn enable_styles(color_when: ColorWhen) -> bool {
+    match color_when {
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+        ColorWhen::Auto => {
+            std::env::var_os("NO_COLOR").is_none() && atty::is(Stream::Stdout)
+        }
+    }
+}
+
+/// Central "plain output" gate, modeled after Mercurial's `PLAIN`/`PLAINEXCEPT` mechanism.
+///
+/// When `TEALDEER_PLAIN` is set, colors, the pager and the cache-freshness banner are all
+/// disabled regardless of config file or flags. `TEALDEER_PLAINEXCEPT` is a comma-separated
+/// list of categories (`color`, `pager`, `banner`) to leave alone.
+struct PlainOverrides {
+    disable_color: bool,
+    disable_pager: bool,
+    suppress_cache_banner: bool,
+}
+
+impl PlainOverrides {
+    fn resolve() -> Self {
+        if std::env::var_os("TEALDEER_PLAIN").is_none() {
+            return Self {
+                disable_color: false,
+                disable_pager: false,
+                suppress_cache_banner: false,
+            };
+        }
+
+        let exceptions: Vec<String> = std::env::var("TEALDEER_PLAINEXCEPT")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let excepted = |category: &str| exceptions.iter().any(|e| e == category);
+
+        Self {
+            disable_color: !excepted("color"),
+            disable_pager: !excepted("pager"),
+            suppress_cache_banner: !excepted("banner"),
+        }
+    }
+}
+
 /// Print page by path
 fn print_page(path: &Path, enable_markdown: bool, enable_styles: bool) -> Result<(), String> {
     // Open file
@@ -142,16 +247,43 @@ fn print_page(path: &Path, enable_markdown: bool, enable_styles: bool) -> Result
     Ok(())
 }
 
+/// Check whether the first word of `command` names a file in one of the `$PATH` directories.
+/// The executable permission bit is not checked.
+fn binary_exists(command: &str) -> bool {
+    let binary = match command.split_whitespace().next() {
+        Some(b) => b,
+        None => return false,
+    };
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+        .unwrap_or(false)
+}
+
+/// Resolve the pager command to use, in priority order: the explicit `display.pager_command`
+/// config value, then `$PAGER`/`$MANPAGER`, then the built-in `less -R` default. Any candidate
+/// whose binary can't be found on `$PATH` is skipped in favor of the next one.
+fn resolve_pager_command(config_pager: Option<&str>) -> String {
+    let candidates = [
+        config_pager.map(str::to_string),
+        std::env::var("PAGER").ok(),
+        std::env::var("MANPAGER").ok(),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|candidate| binary_exists(candidate))
+        .unwrap_or_else(|| PAGER_COMMAND.to_string())
+}
+
 /// Set up display pager
 #[cfg(not(target_os = "windows"))]
-fn configure_pager(args: &Arg, enable_styles: bool) {
-    // Flags have precedence
-    if args.flag_pager {
-        Pager::with_default_pager(PAGER_COMMAND).setup();
+fn configure_pager(args: &Arg, enable_styles: bool, disable_pager: bool) {
+    // TEALDEER_PLAIN overrides config file and flags alike.
+    if disable_pager {
         return;
     }
 
-    // Then check config
     let config = match Config::load(enable_styles) {
         Ok(config) => config,
         Err(ConfigError(msg)) => {
@@ -164,32 +296,43 @@ fn configure_pager(args: &Arg, enable_styles: bool) {
         }
     };
 
+    // Flags decide *whether* to page; the configured pager_command (if any) still wins as the
+    // highest-precedence command source, ahead of $PAGER/$MANPAGER/the default.
+    if args.flag_pager {
+        let pager_command = resolve_pager_command(config.display.pager_command.as_deref());
+        Pager::with_default_pager(&pager_command).setup();
+        return;
+    }
+
     if config.display.use_pager {
-        Pager::with_default_pager(PAGER_COMMAND).setup();
+        let pager_command = resolve_pager_command(config.display.pager_command.as_deref());
+        Pager::with_default_pager(&pager_command).setup();
     }
 }
 
 #[cfg(target_os = "windows")]
-fn configure_pager(_args: &Arg, _enable_styles: bool) {
+fn configure_pager(_args: &Arg, _enable_styles: bool, _disable_pager: bool) {
     eprintln!("Warning: -p / --pager flag not available on Windows!");
 }
 
 /// Check the cache for freshness
-fn check_cache(args: &Arg) {
+fn check_cache(args: &Arg, styles_enabled: bool, suppress_banner: bool) {
     if !args.flag_update {
         match Cache::last_update() {
             Some(ago) if ago > MAX_CACHE_AGE => {
-                if args.flag_quiet {
+                if args.flag_quiet || suppress_banner {
                     return;
                 }
-                println!(
-                    "{}",
-                    Color::Yellow.paint(format!(
-                        "The cache hasn't been updated for more than {} days.\n\
-                         You should probably run `tldr --update` soon.",
-                        MAX_CACHE_AGE.as_secs() / 24 / 3600
-                    ))
+                let message = format!(
+                    "The cache hasn't been updated for more than {} days.\n\
+                     You should probably run `tldr --update` soon.",
+                    MAX_CACHE_AGE.as_secs() / 24 / 3600
                 );
+                if styles_enabled {
+                    println!("{}", Color::Yellow.paint(message));
+                } else {
+                    println!("{}", message);
+                }
             }
             None => {
                 eprintln!("Cache not found. Please run `tldr --update`.");
@@ -306,19 +449,35 @@ fn get_os() -> OsType {
     OsType::Other
 }
 
-fn main() {
-    // Initialize logger
-    init_log();
+/// Build the clap `App`. Pulled out into its own function so that both argument parsing and
+/// `completions` subcommand generation build the exact same CLI definition.
+///
+/// `cached_pages`, when non-empty (i.e. the cache has been populated), is attached to the
+/// `command` argument as `possible_values` so that generated shell completions can offer the
+/// cached command names.
+fn build_cli<'a>(cached_pages: &'a [String]) -> App<'a, 'a> {
+    let mut command_arg = Arg::with_name("command")
+        .help("Sets the command to tldr")
+        .required(false)
+        .index(1);
+    if !cached_pages.is_empty() {
+        command_arg = command_arg.possible_values(
+            &cached_pages.iter().map(String::as_str).collect::<Vec<&str>>(),
+        );
+    }
 
-    // Parse arguments
-    let args = App::new("tldr")
+    App::new("tldr")
                           .version("1.3.1")
                           .author("tealdeer")
                           .about("tldr - Simplified and community-driven man pages")
-                          .arg(Arg::with_name("command")
-                               .help("Sets the command to tldr")
-                               .required(false)
-                               .index(1))
+                          .subcommand(SubCommand::with_name("completions")
+                               .about("Generate shell completion scripts")
+                               .arg(Arg::with_name("shell")
+                                    .help("The shell to generate completions for")
+                                    .required(true)
+                                    .possible_values(&["bash", "zsh", "fish", "powershell", "elvish"])
+                                    .index(1)))
+                          .arg(command_arg)
                           .arg(Arg::with_name("help")
                                .short("h")
                                .long("help")
@@ -327,6 +486,9 @@ fn main() {
                                .short("v")
                                .long("version")
                                .help("Show version information"))
+                          .arg(Arg::with_name("verbose")
+                               .long("verbose")
+                               .help("Used with --version, show build metadata (commit, target, features)"))
                           .arg(Arg::with_name("list")
                                .short("l")
                                .long("list")
@@ -341,6 +503,11 @@ fn main() {
                                .long("os")
                                .value_name("type")
                                .help("Override the operating system [linux, osx, sunos, windows]"))
+                          .arg(Arg::with_name("language")
+                               .short("L")
+                               .long("language")
+                               .value_name("lang")
+                               .help("Override the language (e.g. `de`, `pt_BR`)"))
                           .arg(Arg::with_name("update")
                                .short("u")
                                .long("update")
@@ -357,6 +524,11 @@ fn main() {
                                .short("m")
                                .long("markdown")
                                .help("Display the raw markdown instead of rendering it"))
+                          .arg(Arg::with_name("color")
+                               .long("color")
+                               .value_name("when")
+                               .possible_values(&["auto", "always", "never"])
+                               .help("Control whether to use colors [auto, always, never]"))
                           .arg(Arg::with_name("quiet")
                                .short("q")
                                .long("quiet")
@@ -367,23 +539,70 @@ fn main() {
                           .arg(Arg::with_name("seed_config")
                                .long("seed-config")
                                .help("Create a basic config"))
-                          .get_matches();
-    
+}
+
+fn main() {
+    // Initialize logger
+    init_log();
+
+    // Parse arguments. The `command` arg's possible values are left unset here (no cache has
+    // been read yet at this point), so any command name is accepted; the cached names are only
+    // attached when generating completions.
+    let args = build_cli(&[]).get_matches();
+
+    // Generate shell completions and exit. Done before anything else touches the cache
+    // directory, since this should work even if the cache has never been populated.
+    if let Some(matches) = args.subcommand_matches("completions") {
+        let shell_name = matches.value_of("shell").expect("required by clap");
+        let cached_pages = Cache::new(ARCHIVE_URL, get_os())
+            .list_pages(&[])
+            .unwrap_or_default();
+        completions::generate(&mut build_cli(&cached_pages), shell_name);
+        process::exit(0);
+    }
+
     // Show version and exit
     if args.is_present("version") {
         let os = get_os();
-        println!("{} v{} ({})", NAME, VERSION, os);
+        if args.is_present("verbose") {
+            println!(
+                "{} v{} ({})\n\
+                 commit hash: {}\n\
+                 build date: {}\n\
+                 target triple: {}\n\
+                 rustc version: {}\n\
+                 features: {}",
+                NAME,
+                VERSION,
+                os,
+                built_info::GIT_COMMIT_HASH.unwrap_or("unknown"),
+                built_info::BUILT_TIME_UTC,
+                built_info::TARGET,
+                built_info::RUSTC_VERSION,
+                built_info::FEATURES_STR,
+            );
+        } else {
+            println!("{} v{} ({})", NAME, VERSION, os);
+        }
         process::exit(0);
     }
 
+    // Resolve the TEALDEER_PLAIN/TEALDEER_PLAINEXCEPT overrides once, up front.
+    let plain = PlainOverrides::resolve();
+
     // Determine the usage of styles
+    let color_when = args
+        .value_of("color")
+        .map(|s| s.parse().expect("validated by clap possible_values"))
+        .unwrap_or(ColorWhen::Auto);
     #[cfg(target_os = "windows")]
-    let enable_styles = ansi_term::enable_ansi_support().is_ok();
+    let styles_enabled =
+        ansi_term::enable_ansi_support().is_ok() && enable_styles(color_when) && !plain.disable_color;
     #[cfg(not(target_os = "windows"))]
-    let enable_styles = true;
+    let styles_enabled = enable_styles(color_when) && !plain.disable_color;
 
     // Configure pager
-    configure_pager(&args, enable_styles);
+    configure_pager(&args, styles_enabled, plain.disable_pager);
 
     // Specify target OS
     let os: OsType = match args.value_of("os") {
@@ -394,6 +613,9 @@ fn main() {
     // Initialize cache
     let cache = Cache::new(ARCHIVE_URL, os);
 
+    // Resolve the language fallback chain (--language flag, then LANGUAGE/LANG env vars)
+    let languages = resolve_languages(&args.value_of("language").map(str::to_string));
+
     // Clear cache, pass through
     if args.flag_clear_cache {
         clear_cache(args.value_of("quiet"));
@@ -417,7 +639,7 @@ fn main() {
     // Render local file and exit
     if let Some(ref file) = args.value_of("render") {
         let path = PathBuf::from(file);
-        if let Err(msg) = print_page(&path, args.value_of("markdown"), enable_styles) {
+        if let Err(msg) = print_page(&path, args.value_of("markdown"), styles_enabled) {
             eprintln!("{}", msg);
             process::exit(1);
         } else {
@@ -428,10 +650,10 @@ fn main() {
     // List cached commands and exit
     if args.flag_list {
         // Check cache for freshness
-        check_cache(&args);
+        check_cache(&args, styles_enabled, plain.suppress_cache_banner);
 
         // Get list of pages
-        let pages = cache.list_pages().unwrap_or_else(|e| {
+        let pages = cache.list_pages(&languages).unwrap_or_else(|e| {
             match e {
                 CacheError(msg) | ConfigError(msg) | UpdateError(msg) => {
                     eprintln!("Could not get list of pages: {}", msg)
@@ -449,11 +671,11 @@ fn main() {
     if let Some(ref command) = args.value_of("command") {
         let command = command.join("-");
         // Check cache for freshness
-        check_cache(&args);
+        check_cache(&args, styles_enabled, plain.suppress_cache_banner);
 
         // Search for command in cache
-        if let Some(path) = cache.find_page(&command) {
-            if let Err(msg) = print_page(&path, args.value_of("markdown"), enable_styles) {
+        if let Some(path) = cache.find_page(&command, &languages) {
+            if let Err(msg) = print_page(&path, args.value_of("markdown"), styles_enabled) {
                 eprintln!("{}", msg);
                 process::exit(1);
             } else {
@@ -478,8 +700,14 @@ fn main() {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
     use docopt::{Docopt, Error};
-    use crate::{Args, OsType, USAGE};
+
+    use crate::{
+        binary_exists, enable_styles, resolve_languages, resolve_pager_command, Args, ColorWhen,
+        OsType, PlainOverrides, USAGE,
+    };
 
     fn test_helper(argv: &[&str]) -> Result<Args, Error> {
         Docopt::new(USAGE).and_then(|d| d.argv(argv.iter()).deserialize())
@@ -497,4 +725,126 @@ mod test {
         let argv = vec!["cp", "--os", "lindows"];
         assert!(!test_helper(&argv).is_ok());
     }
+
+    // `LANG`/`LANGUAGE`/`NO_COLOR`/`TEALDEER_PLAIN*` tests mutate process-wide environment
+    // variables, so they're serialized behind this lock to avoid racing each other.
+    static ENV_MUTEX: Mutex<()> = Mutex::new(());
+
+    fn clear_locale_env() {
+        std::env::remove_var("LANGUAGE");
+        std::env::remove_var("LANG");
+    }
+
+    fn clear_plain_env() {
+        std::env::remove_var("TEALDEER_PLAIN");
+        std::env::remove_var("TEALDEER_PLAINEXCEPT");
+    }
+
+    #[test]
+    fn test_resolve_languages_flag_takes_precedence() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        clear_locale_env();
+        std::env::set_var("LANGUAGE", "fr");
+        assert_eq!(
+            resolve_languages(&Some("de".to_string())),
+            vec!["de".to_string()]
+        );
+        clear_locale_env();
+    }
+
+    #[test]
+    fn test_resolve_languages_language_env_priority_list() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        clear_locale_env();
+        std::env::set_var("LANGUAGE", "de:fr");
+        std::env::set_var("LANG", "es_ES.UTF-8");
+        assert_eq!(
+            resolve_languages(&None),
+            vec!["de".to_string(), "fr".to_string()]
+        );
+        clear_locale_env();
+    }
+
+    #[test]
+    fn test_resolve_languages_lang_with_territory_falls_back_to_bare_prefix() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        clear_locale_env();
+        std::env::set_var("LANG", "de_DE.UTF-8");
+        assert_eq!(
+            resolve_languages(&None),
+            vec!["de_DE".to_string(), "de".to_string()]
+        );
+        clear_locale_env();
+    }
+
+    #[test]
+    fn test_resolve_languages_no_env_falls_back_to_english() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        clear_locale_env();
+        assert_eq!(resolve_languages(&None), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_enable_styles_always_and_never_ignore_no_color() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        std::env::set_var("NO_COLOR", "1");
+        assert!(enable_styles(ColorWhen::Always));
+        assert!(!enable_styles(ColorWhen::Never));
+        std::env::remove_var("NO_COLOR");
+    }
+
+    #[test]
+    fn test_enable_styles_auto_respects_no_color() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        std::env::set_var("NO_COLOR", "1");
+        assert!(!enable_styles(ColorWhen::Auto));
+        std::env::remove_var("NO_COLOR");
+    }
+
+    #[test]
+    fn test_plain_overrides_unset_is_a_no_op() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        clear_plain_env();
+        let overrides = PlainOverrides::resolve();
+        assert!(!overrides.disable_color);
+        assert!(!overrides.disable_pager);
+        assert!(!overrides.suppress_cache_banner);
+    }
+
+    #[test]
+    fn test_plain_overrides_plainexcept_whitelists_categories() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        clear_plain_env();
+        std::env::set_var("TEALDEER_PLAIN", "1");
+        std::env::set_var("TEALDEER_PLAINEXCEPT", "color, pager");
+        let overrides = PlainOverrides::resolve();
+        assert!(!overrides.disable_color);
+        assert!(!overrides.disable_pager);
+        assert!(overrides.suppress_cache_banner);
+        clear_plain_env();
+    }
+
+    #[test]
+    fn test_binary_exists() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        assert!(binary_exists("sh"));
+        assert!(!binary_exists("this-binary-should-not-exist-anywhere"));
+    }
+
+    #[test]
+    fn test_resolve_pager_command_precedence() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        std::env::remove_var("PAGER");
+        std::env::remove_var("MANPAGER");
+        assert_eq!(resolve_pager_command(None), "less -R");
+
+        std::env::set_var("PAGER", "sh");
+        assert_eq!(resolve_pager_command(None), "sh");
+
+        std::env::set_var("MANPAGER", "sh -c true");
+        assert_eq!(resolve_pager_command(Some("sh -c false")), "sh -c false");
+
+        std::env::remove_var("PAGER");
+        std::env::remove_var("MANPAGER");
+    }
 }
@@ -0,0 +1,85 @@
+//! Types used to communicate between the different tealdeer modules.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// The operating system that a page applies to, as used by the `pages[.<lang>]/<os>` cache
+/// subdirectories and the `-o`/`--os` override flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OsType {
+    Linux,
+    OsX,
+    Sunos,
+    Windows,
+    Other,
+}
+
+impl fmt::Display for OsType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            OsType::Linux => "linux",
+            OsType::OsX => "osx",
+            OsType::Sunos => "sunos",
+            OsType::Windows => "windows",
+            OsType::Other => "other",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Tri-state color setting for the `--color` flag, mirroring the `Auto`/`Always`/`Never`
+/// model used by rustc's `ColorConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorWhen {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorWhen {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorWhen::Auto),
+            "always" => Ok(ColorWhen::Always),
+            "never" => Ok(ColorWhen::Never),
+            other => Err(format!("Unknown color mode: {}", other)),
+        }
+    }
+}
+
+impl FromStr for OsType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "linux" => Ok(OsType::Linux),
+            "osx" | "macos" => Ok(OsType::OsX),
+            "sunos" => Ok(OsType::Sunos),
+            "windows" => Ok(OsType::Windows),
+            other => Err(format!("Unknown operating system: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ColorWhen;
+
+    #[test]
+    fn test_color_when_from_str_is_case_insensitive() {
+        assert_eq!("Auto".parse(), Ok(ColorWhen::Auto));
+        assert_eq!("ALWAYS".parse(), Ok(ColorWhen::Always));
+        assert_eq!("never".parse(), Ok(ColorWhen::Never));
+    }
+
+    #[test]
+    fn test_color_when_from_str_rejects_unknown() {
+        assert!("sometimes".parse::<ColorWhen>().is_err());
+    }
+}